@@ -1,5 +1,6 @@
 use anyhow::{bail, Context, Result};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs::{self, File};
 use std::io::Write;
@@ -7,26 +8,191 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 use walkdir::WalkDir;
 
+mod nbt;
+
 // --- Configuration ---
 const SERVER_JAR: &str = "./server.jar";
 const WORK_DIR: &str = "./temp_data";
 const OUTPUT_DIR: &str = "./registries";
+const CACHE_FILE: &str = "./registries.cache.json";
+
+/// Registries the configuration-phase Registry Data packet (0x07) must carry inline NBT
+/// data for, rather than just identifiers. Everything else stays "Has Data = false".
+const DATA_REGISTRIES: &[&str] = &[
+    "dimension_type",
+    "worldgen/biome",
+    "chat_type",
+    "trim_pattern",
+    "trim_material",
+    "wolf_variant",
+    "painting_variant",
+    "damage_type",
+    "banner_pattern",
+    "enchantment",
+];
+
+/// Selects which output artifacts `compile_registries`/`compile_tags` produce: the raw
+/// packet-body `.bin` files, a Valence-style JSON export, or both.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Bin,
+    Json,
+    Both,
+}
+
+impl OutputFormat {
+    fn includes_bin(self) -> bool {
+        matches!(self, OutputFormat::Bin | OutputFormat::Both)
+    }
+
+    fn includes_json(self) -> bool {
+        matches!(self, OutputFormat::Json | OutputFormat::Both)
+    }
+}
+
+/// Per-stage fingerprints recorded after a successful run, so the next invocation can skip
+/// the (slow) Java data generation and, if nothing relevant changed, the recompile too.
+#[derive(Serialize, Deserialize, Default, PartialEq, Eq)]
+struct CacheManifest {
+    jar_hash: String,
+    format: String,
+}
+
+fn load_cache_manifest() -> Option<CacheManifest> {
+    let content = fs::read_to_string(CACHE_FILE).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save_cache_manifest(manifest: &CacheManifest) -> Result<()> {
+    let content = serde_json::to_string_pretty(manifest)?;
+    fs::write(CACHE_FILE, content)?;
+    Ok(())
+}
+
+fn hash_file(path: &Path) -> Result<String> {
+    let bytes = fs::read(path).with_context(|| format!("Reading {} for hashing", path.display()))?;
+    let digest = Sha256::digest(&bytes);
+    Ok(digest.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+fn format_label(format: OutputFormat) -> &'static str {
+    match format {
+        OutputFormat::Bin => "bin",
+        OutputFormat::Json => "json",
+        OutputFormat::Both => "both",
+    }
+}
+
+/// Parses `--format <bin|json|both>` from the CLI args, defaulting to `bin` to preserve
+/// the tool's original behavior when the flag is omitted.
+fn parse_format(args: &[String]) -> Result<OutputFormat> {
+    let Some(idx) = args.iter().position(|a| a == "--format") else {
+        return Ok(OutputFormat::Bin);
+    };
+    let value = args.get(idx + 1).context("--format requires a value (bin|json|both)")?;
+    match value.as_str() {
+        "bin" => Ok(OutputFormat::Bin),
+        "json" => Ok(OutputFormat::Json),
+        "both" => Ok(OutputFormat::Both),
+        other => bail!("Unknown --format value '{}', expected bin|json|both", other),
+    }
+}
+
+/// Shape of the vanilla data generator's `generated/reports/registries.json`, which maps
+/// every registry to its entries along with the authoritative network protocol id.
+#[derive(Deserialize)]
+struct RegistryReportEntry {
+    protocol_id: i32,
+}
+
+#[derive(Deserialize)]
+struct RegistryReportRegistry {
+    entries: BTreeMap<String, RegistryReportEntry>,
+}
+
+type RegistriesReport = BTreeMap<String, RegistryReportRegistry>;
+
+/// Map<RegistryID, Map<TagID, Vec<(Value, Required)>>> of raw (unresolved) tag definitions.
+type RawTags = BTreeMap<String, BTreeMap<String, Vec<(String, bool)>>>;
+
+fn load_registries_report(work_dir: &Path) -> Result<Option<RegistriesReport>> {
+    let report_path = work_dir.join("generated/reports/registries.json");
+    if !report_path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&report_path).context("Reading registries.json report")?;
+    let report: RegistriesReport = serde_json::from_str(&content).context("Parsing registries.json report")?;
+    Ok(Some(report))
+}
 
 #[derive(Deserialize)]
 struct TagFile {
-    values: Vec<String>,
+    values: Vec<TagEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TagEntry {
+    Plain(String),
+    Required {
+        id: String,
+        #[serde(default = "default_required")]
+        required: bool,
+    },
+}
+
+// Vanilla's tag schema treats `required` as optional on object-form entries, defaulting to
+// `true` (missing entries are fatal) when the key is absent.
+fn default_required() -> bool {
+    true
+}
+
+impl TagEntry {
+    fn value(&self) -> &str {
+        match self {
+            TagEntry::Plain(v) => v,
+            TagEntry::Required { id, .. } => id,
+        }
+    }
+
+    fn required(&self) -> bool {
+        match self {
+            TagEntry::Plain(_) => true,
+            TagEntry::Required { required, .. } => *required,
+        }
+    }
 }
 
 fn main() -> Result<()> {
     println!("--- Minecraft Registry & Tag Builder ---");
 
-    // 1. Generate Data using Java
+    let args: Vec<String> = std::env::args().collect();
+    let format = parse_format(&args)?;
+
     if !Path::new(SERVER_JAR).exists() {
         bail!("{} not found!", SERVER_JAR);
     }
-    generate_data()?;
 
-    let data_path = Path::new(WORK_DIR).join("generated/data/minecraft");
+    let jar_hash = hash_file(Path::new(SERVER_JAR))?;
+    let data_path = Path::new(WORK_DIR).join("generated/data");
+    let previous = load_cache_manifest();
+
+    let current = CacheManifest { jar_hash: jar_hash.clone(), format: format_label(format).to_string() };
+
+    if previous.as_ref() == Some(&current) && data_path.exists() && Path::new(OUTPUT_DIR).exists() {
+        println!("--- Up to date (server.jar and format unchanged) - nothing to do ---");
+        return Ok(());
+    }
+
+    // 1. Generate Data using Java (skipped if the server.jar hash is unchanged and the
+    // previously generated tree is still on disk)
+    let jar_unchanged = previous.as_ref().is_some_and(|p| p.jar_hash == jar_hash) && data_path.exists();
+    if jar_unchanged {
+        println!("server.jar unchanged, skipping Java data generation.");
+    } else {
+        generate_data()?;
+    }
+
     if !data_path.exists() {
         bail!("Data generation failed.");
     }
@@ -42,12 +208,16 @@ fn main() -> Result<()> {
     // Map<RegistryID, Map<EntryID, ProtocolID>>
     let mut registry_mappings: HashMap<String, HashMap<String, i32>> = HashMap::new();
 
+    let registries_report = load_registries_report(Path::new(WORK_DIR))?;
+
     println!("Processing Registries...");
-    compile_registries(&data_path, &mut registry_mappings)?;
+    compile_registries(&data_path, &mut registry_mappings, registries_report.as_ref(), format)?;
 
     // 4. Process Tags
     println!("Processing Tags...");
-    compile_tags(&data_path.join("tags"), &registry_mappings)?;
+    compile_tags(&data_path, &registry_mappings, format)?;
+
+    save_cache_manifest(&current)?;
 
     println!("--- Success! Output in {} ---", OUTPUT_DIR);
     Ok(())
@@ -72,11 +242,20 @@ fn generate_data() -> Result<()> {
     Ok(())
 }
 
-fn compile_registries(base_path: &Path, mappings: &mut HashMap<String, HashMap<String, i32>>) -> Result<()> {
-    // 1. Find all registries and their entries
-    let mut registries: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
-
-    for entry in WalkDir::new(base_path).min_depth(1).into_iter().filter_map(|e| e.ok()) {
+fn compile_registries(
+    base_path: &Path,
+    mappings: &mut HashMap<String, HashMap<String, i32>>,
+    registries_report: Option<&RegistriesReport>,
+    format: OutputFormat,
+) -> Result<()> {
+    // 1. Find all registries and their entries (keeping each entry's source file path, so
+    // the opted-in registries below can read their JSON back for NBT encoding)
+    let mut registries: BTreeMap<String, BTreeMap<String, PathBuf>> = BTreeMap::new();
+    let mut registry_names: BTreeMap<String, String> = BTreeMap::new();
+
+    // base_path is `generated/data`, one level above the per-namespace folders (`minecraft`,
+    // or a mod/datapack's own namespace) - walk all of them instead of assuming `minecraft`.
+    for entry in WalkDir::new(base_path).min_depth(2).into_iter().filter_map(|e| e.ok()) {
         let path = entry.path();
         if path.extension().map_or(false, |e| e == "json") {
             // Check if this file is inside a "tags" folder, if so, skip it (handled later)
@@ -85,14 +264,19 @@ fn compile_registries(base_path: &Path, mappings: &mut HashMap<String, HashMap<S
             }
 
             let relative = path.strip_prefix(base_path)?;
-            if let Some(parent) = relative.parent() {
+            let mut components = relative.components();
+            let namespace = components.next().unwrap().as_os_str().to_string_lossy().to_string();
+            let rest = components.as_path();
+
+            if let Some(parent) = rest.parent() {
                 let registry_name = parent.to_string_lossy().replace("\\", "/");
                 let entry_name = path.file_stem().unwrap().to_string_lossy().to_string();
 
-                let full_reg = format!("minecraft:{}", registry_name);
-                let full_entry = format!("minecraft:{}", entry_name);
+                let full_reg = format!("{}:{}", namespace, registry_name);
+                let full_entry = format!("{}:{}", namespace, entry_name);
 
-                registries.entry(full_reg).or_default().insert(full_entry);
+                registry_names.insert(full_reg.clone(), registry_name);
+                registries.entry(full_reg).or_default().insert(full_entry, path.to_path_buf());
             }
         }
     }
@@ -105,92 +289,251 @@ fn compile_registries(base_path: &Path, mappings: &mut HashMap<String, HashMap<S
         write_string(&mut buffer, &reg_id)?;
         write_varint(&mut buffer, entries.len() as i32)?;
 
-        // Entries
+        // Entries are sent in packet order and the client assigns ids positionally, so the
+        // write order must match the authoritative protocol_id when the report has one.
+        // Only registries absent from the report fall back to alphabetical indices.
+        let report_entries = registries_report.and_then(|r| r.get(&reg_id)).map(|r| &r.entries);
+
+        let mut ordered: Vec<(String, i32)> = if let Some(report_entries) = report_entries {
+            let mut known: Vec<(String, i32)> = entries
+                .keys()
+                .filter_map(|e| report_entries.get(e).map(|r| (e.clone(), r.protocol_id)))
+                .collect();
+            known.sort_by_key(|(_, id)| *id);
+
+            // Entries present on disk but missing from the report (unexpected, but possible
+            // for custom data) are appended after the known ids, alphabetically.
+            let next_id = known.last().map_or(0, |(_, id)| id + 1);
+            let unknown = entries.keys().filter(|e| !report_entries.contains_key(*e));
+            known.extend(unknown.enumerate().map(|(i, e)| (e.clone(), next_id + i as i32)));
+            known
+        } else {
+            entries.keys().enumerate().map(|(idx, e)| (e.clone(), idx as i32)).collect()
+        };
+        ordered.sort_by_key(|(_, id)| *id);
+
+        // Registries that need inline entry data read the entry's generated JSON and embed
+        // it as network NBT; everything else stays "Has Data = false" for a lighter packet.
+        let registry_kind = registry_names.get(&reg_id);
+        let include_data = registry_kind.is_some_and(|name| DATA_REGISTRIES.contains(&name.as_str()));
+
         let mut id_map = HashMap::new();
-        for (idx, entry_id) in entries.iter().enumerate() {
-            // Write to packet
-            write_string(&mut buffer, &entry_id)?;
-            buffer.push(0x00); // Has Data? -> False
+        for (entry_id, protocol_id) in &ordered {
+            write_string(&mut buffer, entry_id)?;
+
+            if include_data {
+                let path = &entries[entry_id];
+                let content = fs::read_to_string(path).with_context(|| format!("Reading entry data for {}", entry_id))?;
+                let value: serde_json::Value = serde_json::from_str(&content).with_context(|| format!("Parsing entry data for {}", entry_id))?;
+
+                buffer.push(0x01); // Has Data? -> True
+                buffer.push(0x0A); // Unnamed root TAG_Compound
+                nbt::write_root_compound(&mut buffer, &value, registry_kind.map_or("", |s| s.as_str()))?;
+            } else {
+                buffer.push(0x00); // Has Data? -> False
+            }
 
             // Store ID for mapping (Tags need this ID)
-            id_map.insert(entry_id.clone(), idx as i32);
+            id_map.insert(entry_id.clone(), *protocol_id);
         }
 
         mappings.insert(reg_id.clone(), id_map);
 
-        // Save file
-        let filename = format!("{}.bin", reg_id.replace(":", "_").replace("/", "_"));
-        fs::write(Path::new(OUTPUT_DIR).join(filename), &buffer)?;
+        if format.includes_bin() {
+            let filename = format!("{}.bin", reg_id.replace(":", "_").replace("/", "_"));
+            fs::write(Path::new(OUTPUT_DIR).join(filename), &buffer)?;
+        }
+    }
+
+    if format.includes_json() {
+        let registries_json: BTreeMap<&String, BTreeMap<&String, i32>> = mappings
+            .iter()
+            .map(|(reg, entries)| (reg, entries.iter().map(|(e, id)| (e, *id)).collect()))
+            .collect();
+        let content = serde_json::to_string_pretty(&registries_json)?;
+        fs::write(Path::new(OUTPUT_DIR).join("registries.json"), content)?;
     }
+
     Ok(())
 }
 
-fn compile_tags(tags_path: &Path, registry_mappings: &HashMap<String, HashMap<String, i32>>) -> Result<()> {
-    // Map<RegistryID, Map<TagID, Vec<Integers>>>
-    let mut tags_packet_data: BTreeMap<String, BTreeMap<String, Vec<i32>>> = BTreeMap::new();
+fn compile_tags(data_path: &Path, registry_mappings: &HashMap<String, HashMap<String, i32>>, format: OutputFormat) -> Result<()> {
+    // Pass 1: collect the raw tag definitions (values kept verbatim, including "#other_tag" refs).
+    // Map<RegistryID, Map<TagID, Vec<(Value, Required)>>>
+    let mut raw_tags: RawTags = BTreeMap::new();
+
+    // Registry "kinds" (the namespace-less part of a registry id, e.g. "item") that
+    // `compile_registries` actually populated in some namespace. Every vanilla data
+    // generation writes `tags/function/...` and `tags/point_of_interest_type/...` even
+    // though `function` (`.mcfunction` files) and `point_of_interest_type` aren't
+    // data-driven registries the tool ever compiles - skip those tag folders entirely
+    // instead of collecting tag data we can never resolve ids for.
+    let known_kinds: BTreeSet<&str> = registry_mappings.keys().map(|k| registry_kind(k)).collect();
+
+    // Tags live under <namespace>/tags/<registry>/... for every namespace, not just `minecraft`.
+    for namespace_entry in fs::read_dir(data_path).with_context(|| format!("Reading {}", data_path.display()))? {
+        let namespace_entry = namespace_entry?;
+        if !namespace_entry.file_type()?.is_dir() {
+            continue;
+        }
+        let namespace = namespace_entry.file_name().to_string_lossy().to_string();
+        let tags_path = namespace_entry.path().join("tags");
+        if !tags_path.exists() {
+            continue;
+        }
 
-    for entry in WalkDir::new(tags_path).into_iter().filter_map(|e| e.ok()) {
-        let path = entry.path();
-        if path.extension().map_or(false, |e| e == "json") {
-            let relative = path.strip_prefix(tags_path)?;
-
-            // Format: <registry>/<tag>.json OR <registry>/<sub_path>/<tag>.json
-            if let Some(parent) = relative.parent() {
-                let registry_suffix = parent.components().next().unwrap().as_os_str().to_string_lossy();
-                let full_reg = format!("minecraft:{}", registry_suffix);
-
-                // If we don't have this registry in our mappings, we can't build tags for it.
-                if !registry_mappings.contains_key(&full_reg) { continue; }
-
-                // Tag name is everything after the registry folder
-                let tag_suffix = relative.strip_prefix(&*registry_suffix)?.with_extension("").to_string_lossy().replace("\\", "/");
-                let full_tag = format!("minecraft:{}", tag_suffix);
-
-                // Parse JSON
-                let content = fs::read_to_string(path)?;
-                let parsed: TagFile = serde_json::from_str(&content).unwrap_or(TagFile { values: vec![] });
-
-                let mut ids = Vec::new();
-                for value in parsed.values {
-                    // Simple resolution: direct reference only.
-                    // (Vanilla tags sometimes use # for nested tags,
-                    // this simple parser skips them to prevent complexity,
-                    // which is usually fine for the "required" registry tags)
-                    if !value.starts_with("#") {
-                        if let Some(id) = registry_mappings.get(&full_reg).and_then(|m| m.get(&value)) {
-                            ids.push(*id);
-                        }
+        for entry in WalkDir::new(&tags_path).into_iter().filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.extension().map_or(false, |e| e == "json") {
+                let relative = path.strip_prefix(&tags_path)?;
+
+                // Format: <registry>/<tag>.json OR <registry>/<sub_path>/<tag>.json
+                if let Some(parent) = relative.parent() {
+                    let registry_suffix = parent.components().next().unwrap().as_os_str().to_string_lossy();
+
+                    // If no namespace ever populated this registry kind, we can't resolve
+                    // any id for its tags - skip it rather than building unresolvable data.
+                    if !known_kinds.contains(registry_suffix.as_ref()) {
+                        continue;
                     }
-                }
 
-                tags_packet_data.entry(full_reg).or_default().insert(full_tag, ids);
+                    let full_reg = format!("{}:{}", namespace, registry_suffix);
+
+                    // Tag name is everything after the registry folder
+                    let tag_suffix = relative.strip_prefix(&*registry_suffix)?.with_extension("").to_string_lossy().replace("\\", "/");
+                    let full_tag = format!("{}:{}", namespace, tag_suffix);
+
+                    // Parse JSON
+                    let content = fs::read_to_string(path)?;
+                    let parsed: TagFile = serde_json::from_str(&content).unwrap_or(TagFile { values: vec![] });
+
+                    let values = parsed.values.iter().map(|v| (v.value().to_string(), v.required())).collect();
+                    raw_tags.entry(full_reg).or_default().insert(full_tag, values);
+                }
             }
         }
     }
 
-    // Write "packet_tags.bin" (Packet ID 0x0D body)
-    let mut buffer: Vec<u8> = Vec::new();
+    // Pass 2: resolve each tag into a concrete, de-duplicated id set, recursing through
+    // "#other_tag" references within the same registry kind (in the reference's own
+    // namespace). Resolution is memoized and
+    // cycle-safe (vanilla data packs can technically define cyclic tags).
+    let mut resolved: HashMap<(String, String), BTreeSet<i32>> = HashMap::new();
+    let mut tags_packet_data: BTreeMap<String, BTreeMap<String, Vec<i32>>> = BTreeMap::new();
 
-    // Registry Count
-    write_varint(&mut buffer, tags_packet_data.len() as i32)?;
+    for (reg_name, tags) in &raw_tags {
+        let mut resolved_tags: BTreeMap<String, Vec<i32>> = BTreeMap::new();
+        for tag_name in tags.keys() {
+            let mut resolving = BTreeSet::new();
+            let ids = resolve_tag(reg_name, tag_name, &raw_tags, registry_mappings, &mut resolved, &mut resolving)?;
+            resolved_tags.insert(tag_name.clone(), ids.into_iter().collect());
+        }
+        tags_packet_data.insert(reg_name.clone(), resolved_tags);
+    }
+
+    if format.includes_json() {
+        let content = serde_json::to_string_pretty(&tags_packet_data)?;
+        fs::write(Path::new(OUTPUT_DIR).join("tags.json"), content)?;
+    }
+
+    if format.includes_bin() {
+        // Write "packet_tags.bin" (Packet ID 0x0D body)
+        let mut buffer: Vec<u8> = Vec::new();
 
-    for (reg_name, tags) in tags_packet_data {
-        write_string(&mut buffer, &reg_name)?; // Registry Name
-        write_varint(&mut buffer, tags.len() as i32)?; // Tag Count
+        // Registry Count
+        write_varint(&mut buffer, tags_packet_data.len() as i32)?;
 
-        for (tag_name, ids) in tags {
-            write_string(&mut buffer, &tag_name)?; // Tag Name
-            write_varint(&mut buffer, ids.len() as i32)?; // ID Count
-            for id in ids {
-                write_varint(&mut buffer, id)?; // ID
+        for (reg_name, tags) in tags_packet_data {
+            write_string(&mut buffer, &reg_name)?; // Registry Name
+            write_varint(&mut buffer, tags.len() as i32)?; // Tag Count
+
+            for (tag_name, ids) in tags {
+                write_string(&mut buffer, &tag_name)?; // Tag Name
+                write_varint(&mut buffer, ids.len() as i32)?; // ID Count
+                for id in ids {
+                    write_varint(&mut buffer, id)?; // ID
+                }
             }
         }
+
+        fs::write(Path::new(OUTPUT_DIR).join("packet_tags.bin"), &buffer)?;
     }
 
-    fs::write(Path::new(OUTPUT_DIR).join("packet_tags.bin"), &buffer)?;
     Ok(())
 }
 
+/// Recursively resolves `tag_name` in `reg_name` to a set of protocol ids, following
+/// `#other_tag` references within the same registry kind, looked up in the namespace the
+/// reference names (so it may be another namespace's registry). Already-resolved tags are memoized
+/// in `resolved`; `resolving` tracks the current DFS path so cyclic tag definitions are
+/// broken instead of overflowing the stack.
+fn resolve_tag(
+    reg_name: &str,
+    tag_name: &str,
+    raw_tags: &RawTags,
+    registry_mappings: &HashMap<String, HashMap<String, i32>>,
+    resolved: &mut HashMap<(String, String), BTreeSet<i32>>,
+    resolving: &mut BTreeSet<(String, String)>,
+) -> Result<BTreeSet<i32>> {
+    let key = (reg_name.to_string(), tag_name.to_string());
+    if let Some(ids) = resolved.get(&key) {
+        return Ok(ids.clone());
+    }
+    if resolving.contains(&key) {
+        // Cyclic tag reference: break the cycle by contributing nothing from this branch.
+        return Ok(BTreeSet::new());
+    }
+
+    let Some(entries) = raw_tags.get(reg_name).and_then(|t| t.get(tag_name)) else {
+        return Ok(BTreeSet::new());
+    };
+
+    resolving.insert(key.clone());
+
+    // The registry "kind" (e.g. "block", "worldgen/biome") is shared across namespaces -
+    // a value's own namespace (not this tag's) picks which namespaced registry to look in,
+    // so a `mymod:planks` tag can reference entries/tags from `#minecraft:...` and vice versa.
+    let kind = registry_kind(reg_name);
+
+    let mut ids = BTreeSet::new();
+    for (value, required) in entries {
+        if let Some(referenced_tag) = value.strip_prefix('#') {
+            let target_reg = format!("{}:{}", namespace_of(referenced_tag), kind);
+            let sub_ids = resolve_tag(&target_reg, referenced_tag, raw_tags, registry_mappings, resolved, resolving)?;
+            if sub_ids.is_empty() && !raw_tags.get(&target_reg).is_some_and(|t| t.contains_key(referenced_tag)) {
+                if *required {
+                    bail!("Tag {} in registry {} references missing tag #{}", tag_name, reg_name, referenced_tag);
+                }
+            } else {
+                ids.extend(sub_ids);
+            }
+        } else {
+            let target_reg = format!("{}:{}", namespace_of(value), kind);
+            if let Some(id) = registry_mappings.get(&target_reg).and_then(|m| m.get(value)) {
+                ids.insert(*id);
+            } else if *required {
+                bail!("Tag {} in registry {} references missing entry {}", tag_name, reg_name, value);
+            }
+        }
+    }
+
+    resolving.remove(&key);
+    resolved.insert(key, ids.clone());
+    Ok(ids)
+}
+
+/// The registry "kind" is the portion of a full registry id after its namespace, e.g.
+/// `"block"` for `"minecraft:block"` - shared by every namespace that defines that registry.
+fn registry_kind(full_reg: &str) -> &str {
+    full_reg.split_once(':').map_or(full_reg, |(_, kind)| kind)
+}
+
+/// The namespace of a fully-qualified id such as `"minecraft:logs"`, defaulting to
+/// `"minecraft"` when no namespace is given (vanilla's own shorthand convention).
+fn namespace_of(id: &str) -> &str {
+    id.split_once(':').map_or("minecraft", |(ns, _)| ns)
+}
+
 fn write_varint(buf: &mut Vec<u8>, mut value: i32) -> Result<()> {
     loop {
         let mut temp = (value & 0x7F) as u8;
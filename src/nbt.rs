@@ -0,0 +1,264 @@
+//! Minimal binary NBT writer covering the tag types Minecraft's registry entry data actually
+//! uses. Only what `compile_registries` needs to embed is implemented (no reader, no
+//! compression) - this is not a general-purpose NBT library.
+
+use anyhow::{bail, Result};
+use serde_json::Value;
+
+const TAG_END: u8 = 0;
+const TAG_BYTE: u8 = 1;
+const TAG_INT: u8 = 3;
+const TAG_LONG: u8 = 4;
+const TAG_FLOAT: u8 = 5;
+const TAG_DOUBLE: u8 = 6;
+const TAG_STRING: u8 = 8;
+const TAG_LIST: u8 = 9;
+const TAG_COMPOUND: u8 = 10;
+
+/// Top-level fields whose NBT wire type is fixed by the configuration-phase registry codec
+/// and can't be guessed from JSON shape alone - `(registry_kind, field_name, tag_id)`. JSON
+/// has no float/double distinction, so anything not listed here falls back to the shape-based
+/// guess in `tag_id_for` (Float for fractional numbers), which is correct for most fields but
+/// not these.
+const FIELD_TYPE_OVERRIDES: &[(&str, &str, u8)] = &[
+    ("dimension_type", "coordinate_scale", TAG_DOUBLE),
+    // Optional; only present for dimensions with a fixed time of day (the_end, the_nether).
+    // Small values like `6000` fit in an i32, so the shape-based guess in `tag_id_for` would
+    // otherwise pick TAG_INT instead of the Long the dimension_type codec actually requires.
+    ("dimension_type", "fixed_time", TAG_LONG),
+];
+
+fn forced_tag_id(kind: Option<&str>, field_name: &str) -> Option<u8> {
+    let kind = kind?;
+    FIELD_TYPE_OVERRIDES
+        .iter()
+        .find(|(k, f, _)| *k == kind && *f == field_name)
+        .map(|(_, _, tag_id)| *tag_id)
+}
+
+/// Writes a JSON object as a network NBT compound: unlike file NBT, the root tag is unnamed,
+/// and its `TAG_Compound` type-id byte is written by the caller (after the "Has Data" flag),
+/// so this only emits the compound's named entries followed by `TAG_End`.
+/// `kind` is the registry kind (e.g. `"dimension_type"`) and is consulted for top-level field
+/// type overrides; nested compounds don't carry field overrides of their own.
+pub fn write_root_compound(buf: &mut Vec<u8>, value: &Value, kind: &str) -> Result<()> {
+    let Value::Object(map) = value else {
+        bail!("Registry entry data must be a JSON object to encode as an NBT compound");
+    };
+    write_compound_body(buf, map, Some(kind))
+}
+
+fn write_compound_body(buf: &mut Vec<u8>, map: &serde_json::Map<String, Value>, kind: Option<&str>) -> Result<()> {
+    for (key, val) in map {
+        let tag_id = forced_tag_id(kind, key).unwrap_or_else(|| tag_id_for(val));
+        buf.push(tag_id);
+        write_name(buf, key);
+        write_payload_as(buf, tag_id, val)?;
+    }
+    buf.push(TAG_END);
+    Ok(())
+}
+
+fn tag_id_for(value: &Value) -> u8 {
+    match value {
+        Value::Null | Value::Bool(_) => TAG_BYTE,
+        Value::Number(n) if n.is_i64() || n.is_u64() => {
+            let n = n.as_i64().unwrap_or_else(|| n.as_u64().unwrap() as i64);
+            if n >= i32::MIN as i64 && n <= i32::MAX as i64 { TAG_INT } else { TAG_LONG }
+        }
+        // JSON has no float/double distinction; Minecraft's own registry data (biome
+        // temperature/downfall, etc.) overwhelmingly uses Float for fractional values.
+        // Fields known to require Double are listed in FIELD_TYPE_OVERRIDES instead.
+        Value::Number(_) => TAG_FLOAT,
+        Value::String(_) => TAG_STRING,
+        Value::Array(_) => TAG_LIST,
+        Value::Object(_) => TAG_COMPOUND,
+    }
+}
+
+/// Writes `value`'s payload as the given `tag_id`, rather than re-deriving the tag id from
+/// `value` itself - the caller (a forced field, or a list matching its declared element type)
+/// may need a different numeric width than the JSON shape alone would suggest.
+fn write_payload_as(buf: &mut Vec<u8>, tag_id: u8, value: &Value) -> Result<()> {
+    match tag_id {
+        TAG_BYTE => write_byte(buf, as_bool(value) as i8),
+        TAG_INT => write_int(buf, as_i64(value) as i32),
+        TAG_LONG => write_long(buf, as_i64(value)),
+        TAG_FLOAT => write_float(buf, as_f64(value) as f32),
+        TAG_DOUBLE => write_double(buf, as_f64(value)),
+        TAG_STRING => write_name(buf, value.as_str().unwrap_or_default()),
+        TAG_LIST => write_list_payload(buf, value.as_array().map(Vec::as_slice).unwrap_or_default())?,
+        TAG_COMPOUND => match value {
+            Value::Object(map) => write_compound_body(buf, map, None)?,
+            _ => bail!("Expected a JSON object for an NBT compound field"),
+        },
+        _ => unreachable!("unhandled NBT tag id {}", tag_id),
+    }
+    Ok(())
+}
+
+fn as_bool(value: &Value) -> bool {
+    match value {
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_i64().unwrap_or(0) != 0,
+        _ => false,
+    }
+}
+
+fn as_i64(value: &Value) -> i64 {
+    match value {
+        Value::Number(n) => n.as_i64().unwrap_or_else(|| n.as_u64().unwrap_or(0) as i64),
+        Value::Bool(b) => *b as i64,
+        _ => 0,
+    }
+}
+
+fn as_f64(value: &Value) -> f64 {
+    match value {
+        Value::Number(n) => n.as_f64().unwrap_or(0.0),
+        _ => 0.0,
+    }
+}
+
+fn write_list_payload(buf: &mut Vec<u8>, items: &[Value]) -> Result<()> {
+    // Every element is coerced to the same declared element type (derived from the first
+    // element), since NBT lists are homogeneous on the wire - writing a mixed-type JSON
+    // array element-by-element at its own inferred width would desync every tag after it.
+    let element_tag = items.first().map_or(TAG_END, tag_id_for);
+    buf.push(element_tag);
+    write_int(buf, items.len() as i32);
+    for item in items {
+        write_payload_as(buf, element_tag, item)?;
+    }
+    Ok(())
+}
+
+fn write_name(buf: &mut Vec<u8>, s: &str) {
+    let bytes = s.as_bytes();
+    buf.extend_from_slice(&(bytes.len() as u16).to_be_bytes());
+    buf.extend_from_slice(bytes);
+}
+
+fn write_byte(buf: &mut Vec<u8>, v: i8) {
+    buf.push(v as u8);
+}
+
+fn write_int(buf: &mut Vec<u8>, v: i32) {
+    buf.extend_from_slice(&v.to_be_bytes());
+}
+
+fn write_long(buf: &mut Vec<u8>, v: i64) {
+    buf.extend_from_slice(&v.to_be_bytes());
+}
+
+fn write_float(buf: &mut Vec<u8>, v: f32) {
+    buf.extend_from_slice(&v.to_be_bytes());
+}
+
+fn write_double(buf: &mut Vec<u8>, v: f64) {
+    buf.extend_from_slice(&v.to_be_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn field_tag(buf: &[u8], name: &str) -> u8 {
+        let mut i = 0;
+        while i < buf.len() {
+            let tag_id = buf[i];
+            if tag_id == TAG_END {
+                break;
+            }
+            let name_len = u16::from_be_bytes([buf[i + 1], buf[i + 2]]) as usize;
+            let name_start = i + 3;
+            let field_name = std::str::from_utf8(&buf[name_start..name_start + name_len]).unwrap();
+            let payload_start = name_start + name_len;
+            if field_name == name {
+                return tag_id;
+            }
+            i = payload_start + skip_payload(tag_id, &buf[payload_start..]);
+        }
+        panic!("field {name} not found");
+    }
+
+    // Advances past one payload so `field_tag` can walk a compound without fully parsing it;
+    // only the shapes the test fixtures below actually use need to be handled.
+    fn skip_payload(tag_id: u8, buf: &[u8]) -> usize {
+        match tag_id {
+            TAG_BYTE => 1,
+            TAG_INT => 4,
+            TAG_LONG => 8,
+            TAG_FLOAT => 4,
+            TAG_DOUBLE => 8,
+            TAG_STRING => 2 + u16::from_be_bytes([buf[0], buf[1]]) as usize,
+            _ => panic!("skip_payload: unhandled tag id {tag_id} in test fixture"),
+        }
+    }
+
+    // Real vanilla `the_end.json` dimension_type entry, trimmed to the fields exercised here.
+    const THE_END: &str = r##"{
+        "fixed_time": 6000,
+        "has_skylight": false,
+        "has_ceiling": false,
+        "ultrawarm": false,
+        "natural": false,
+        "coordinate_scale": 1.0,
+        "bed_works": false,
+        "respawn_anchor_works": false,
+        "min_y": 0,
+        "height": 256,
+        "logical_height": 256,
+        "infiniburn": "#minecraft:infiniburn_end",
+        "effects": "minecraft:the_end",
+        "ambient_light": 0.0
+    }"##;
+
+    #[test]
+    fn dimension_type_fixed_time_is_long() {
+        let value: Value = serde_json::from_str(THE_END).unwrap();
+        let mut buf = Vec::new();
+        write_root_compound(&mut buf, &value, "dimension_type").unwrap();
+        assert_eq!(field_tag(&buf, "fixed_time"), TAG_LONG);
+    }
+
+    #[test]
+    fn dimension_type_coordinate_scale_is_double() {
+        let value: Value = serde_json::from_str(THE_END).unwrap();
+        let mut buf = Vec::new();
+        write_root_compound(&mut buf, &value, "dimension_type").unwrap();
+        assert_eq!(field_tag(&buf, "coordinate_scale"), TAG_DOUBLE);
+    }
+
+    #[test]
+    fn dimension_type_fields_without_overrides_keep_shape_based_types() {
+        let value: Value = serde_json::from_str(THE_END).unwrap();
+        let mut buf = Vec::new();
+        write_root_compound(&mut buf, &value, "dimension_type").unwrap();
+        assert_eq!(field_tag(&buf, "min_y"), TAG_INT);
+        assert_eq!(field_tag(&buf, "ambient_light"), TAG_FLOAT);
+        assert_eq!(field_tag(&buf, "has_skylight"), TAG_BYTE);
+        assert_eq!(field_tag(&buf, "infiniburn"), TAG_STRING);
+    }
+
+    // Real vanilla biome entry (temperature/downfall are Floats per the worldgen/biome codec,
+    // so a registry kind with no overrides should still get the right wire types).
+    #[test]
+    fn biome_without_overrides_uses_shape_based_float() {
+        let value = json!({"has_precipitation": true, "temperature": 0.8, "downfall": 0.4});
+        let mut buf = Vec::new();
+        write_root_compound(&mut buf, &value, "worldgen/biome").unwrap();
+        assert_eq!(field_tag(&buf, "temperature"), TAG_FLOAT);
+        assert_eq!(field_tag(&buf, "downfall"), TAG_FLOAT);
+    }
+
+    #[test]
+    fn mixed_type_list_elements_use_declared_width() {
+        let value = json!({"values": [5_000_000_000i64, 1]});
+        let mut buf = Vec::new();
+        write_root_compound(&mut buf, &value, "x").unwrap();
+        // type(1) + name_len(2) + name("values"=6) + elem_type(1) + count(4) + 2 longs(16) + end(1)
+        assert_eq!(buf.len(), 1 + 2 + 6 + 1 + 4 + 16 + 1);
+    }
+}